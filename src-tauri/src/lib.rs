@@ -7,65 +7,12 @@ use tauri::{
     image::Image,
 };
 
-// Get list of available printers
-#[tauri::command]
-fn get_printers() -> Result<Vec<String>, String> {
-    #[cfg(target_os = "macos")]
-    {
-        let output = Command::new("lpstat")
-            .arg("-e")
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let printers: Vec<String> = stdout
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        Ok(printers)
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        // Use PowerShell with WMI (works on all Windows versions including Windows 11)
-        let output = Command::new("powershell.exe")
-            .args([
-                "-NoProfile",
-                "-Command",
-                "Get-WmiObject -Class Win32_Printer | Select-Object -ExpandProperty Name"
-            ])
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let printers: Vec<String> = stdout
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        Ok(printers)
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        let output = Command::new("lpstat")
-            .arg("-e")
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let printers: Vec<String> = stdout
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        Ok(printers)
-    }
-}
+mod logging;
+mod opener;
+mod print_jobs;
+mod printers;
+mod sandbox_env;
+mod updater;
 
 // Print result with details
 #[derive(serde::Serialize)]
@@ -73,11 +20,17 @@ struct PrintResult {
     success: bool,
     size_kb: usize,
     message: String,
+    job_id: Option<String>,
 }
 
 // Print a PDF (base64 encoded)
 #[tauri::command]
-fn print_pdf(pdf_base64: String, printer_name: String, job_name: String) -> Result<PrintResult, String> {
+fn print_pdf(
+    pdf_base64: String,
+    printer_name: String,
+    job_name: String,
+    registry: tauri::State<print_jobs::JobRegistry>,
+) -> Result<PrintResult, String> {
     // Decode base64 to bytes
     let pdf_bytes = base64::engine::general_purpose::STANDARD
         .decode(&pdf_base64)
@@ -92,62 +45,99 @@ fn print_pdf(pdf_base64: String, printer_name: String, job_name: String) -> Resu
     std::fs::write(&pdf_path, &pdf_bytes)
         .map_err(|e| format!("Failed to write PDF: {}", e))?;
 
+    let context = format!(
+        "print_pdf printer={} job={} size_kb={}",
+        printer_name, job_name, size_kb
+    );
+
     // Print using system command
     #[cfg(target_os = "macos")]
     {
-        let output = Command::new("lp")
-            .arg("-d")
+        let mut cmd = Command::new("lp");
+        cmd.arg("-d")
             .arg(&printer_name)
             .arg("-t")
             .arg(&job_name)
-            .arg(&pdf_path)
-            .output()
-            .map_err(|e| format!("Failed to print: {}", e))?;
+            .arg(&pdf_path);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| format!("Failed to print: {}", e))?;
+        logging::log_command_result(&context, &argv, &output);
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(format!("Print failed: {}", stderr));
         }
 
+        let job_id = print_jobs::parse_cups_job_id(&String::from_utf8_lossy(&output.stdout));
+        if let Some(job_id) = &job_id {
+            registry.insert(print_jobs::JobInfo {
+                job_id: job_id.clone(),
+                printer_name: printer_name.clone(),
+                job_name: job_name.clone(),
+                status: print_jobs::JobStatus::Queued,
+            });
+        }
+
         return Ok(PrintResult {
             success: true,
             size_kb,
             message: format!("Printed via lp to {}", printer_name),
+            job_id,
         });
     }
 
     #[cfg(target_os = "windows")]
     {
-        print_pdf_windows(&pdf_path, &printer_name, size_kb)
+        print_pdf_windows(&pdf_path, &printer_name, &job_name, size_kb, &registry)
     }
 
     #[cfg(target_os = "linux")]
     {
-        let output = Command::new("lp")
-            .arg("-d")
+        let mut cmd = Command::new("lp");
+        cmd.arg("-d")
             .arg(&printer_name)
             .arg("-t")
             .arg(&job_name)
-            .arg(&pdf_path)
-            .output()
-            .map_err(|e| format!("Failed to print: {}", e))?;
+            .arg(&pdf_path);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| format!("Failed to print: {}", e))?;
+        logging::log_command_result(&context, &argv, &output);
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(format!("Print failed: {}", stderr));
         }
 
+        let job_id = print_jobs::parse_cups_job_id(&String::from_utf8_lossy(&output.stdout));
+        if let Some(job_id) = &job_id {
+            registry.insert(print_jobs::JobInfo {
+                job_id: job_id.clone(),
+                printer_name: printer_name.clone(),
+                job_name: job_name.clone(),
+                status: print_jobs::JobStatus::Queued,
+            });
+        }
+
         return Ok(PrintResult {
             success: true,
             size_kb,
             message: format!("Printed via lp to {}", printer_name),
+            job_id,
         });
     }
 }
 
 // Print PDF using SumatraPDF on Windows (silent, reliable)
 #[cfg(target_os = "windows")]
-fn print_pdf_windows(pdf_path: &std::path::Path, printer_name: &str, size_kb: usize) -> Result<PrintResult, String> {
+fn print_pdf_windows(
+    pdf_path: &std::path::Path,
+    printer_name: &str,
+    job_name: &str,
+    size_kb: usize,
+    registry: &tauri::State<print_jobs::JobRegistry>,
+) -> Result<PrintResult, String> {
     // Find SumatraPDF.exe - it's bundled next to the executable
     let exe_path = std::env::current_exe()
         .map_err(|e| format!("Failed to get executable path: {}", e))?;
@@ -165,19 +155,40 @@ fn print_pdf_windows(pdf_path: &std::path::Path, printer_name: &str, size_kb: us
 
     // Use SumatraPDF for silent printing
     // Command: SumatraPDF.exe -print-to "printer" -silent file.pdf
-    let output = Command::new(&sumatra_path)
-        .arg("-print-to")
+    let mut cmd = Command::new(&sumatra_path);
+    cmd.arg("-print-to")
         .arg(printer_name)
         .arg("-silent")
-        .arg(pdf_path)
+        .arg(pdf_path);
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = cmd
         .output()
         .map_err(|e| format!("Failed to execute SumatraPDF: {}", e))?;
+    logging::log_command_result(
+        &format!("print_pdf printer={} job={} size_kb={}", printer_name, job_name, size_kb),
+        &argv,
+        &output,
+    );
 
     if output.status.success() {
+        // SumatraPDF doesn't report a spooler job id on stdout, so look it
+        // up from WMI right after submission to populate the live queue.
+        let job_id = print_jobs::capture_windows_job_id(printer_name);
+        if let Some(job_id) = &job_id {
+            registry.insert(print_jobs::JobInfo {
+                job_id: job_id.clone(),
+                printer_name: printer_name.to_string(),
+                job_name: job_name.to_string(),
+                status: print_jobs::JobStatus::Queued,
+            });
+        }
+
         Ok(PrintResult {
             success: true,
             size_kb,
             message: format!("Printed via SumatraPDF to {}", printer_name),
+            job_id,
         })
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -193,21 +204,53 @@ fn print_pdf_windows(pdf_path: &std::path::Path, printer_name: &str, size_kb: us
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Snapshot the pre-launch environment before anything (including Tauri
+    // itself) has a chance to mutate it further.
+    sandbox_env::capture_pristine_env();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(print_jobs::JobRegistry::default())
+        .manage(updater::UpdateState::default())
+        .manage(opener::PreviewRegistry::default())
         .setup(|app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // Logging is always on, including release builds: a headless
+            // tray app with no logs gives support nothing to work with when
+            // a print silently fails.
+            app.handle().plugin(
+                tauri_plugin_log::Builder::default()
+                    .target(tauri_plugin_log::Target::new(
+                        tauri_plugin_log::TargetKind::LogDir {
+                            file_name: Some("gls-print-agent".to_string()),
+                        },
+                    ))
+                    .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
+                    .max_file_size(5_000_000)
+                    .level(log::LevelFilter::Info)
+                    .build(),
+            )?;
 
             // Create system tray menu
             let show_item = MenuItem::with_id(app, "show", "Åbn", true, None::<&str>)?;
+            let open_logs_item = MenuItem::with_id(app, "open_logs", "Åbn logmappe", true, None::<&str>)?;
+            let check_updates_item =
+                MenuItem::with_id(app, "check_updates", "Søg efter opdateringer…", true, None::<&str>)?;
+            let install_update_item =
+                MenuItem::with_id(app, "install_update", "Installer opdatering", true, None::<&str>)?;
             let quit_item = MenuItem::with_id(app, "quit", "Afslut", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let menu = Menu::with_items(
+                app,
+                &[
+                    &show_item,
+                    &open_logs_item,
+                    &check_updates_item,
+                    &install_update_item,
+                    &quit_item,
+                ],
+            )?;
+
+            updater::start_update_checks(app.handle());
 
             // Load tray icon from app icons
             let icon = Image::from_path("icons/32x32.png")
@@ -226,6 +269,27 @@ pub fn run() {
                                 let _ = window.set_focus();
                             }
                         }
+                        "open_logs" => {
+                            if let Err(e) = logging::open_log_folder_handle(app) {
+                                log::error!("Failed to open log folder: {}", e);
+                            }
+                        }
+                        "check_updates" => {
+                            let app = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = updater::check_for_updates_now(app).await {
+                                    log::error!("Update check failed: {}", e);
+                                }
+                            });
+                        }
+                        "install_update" => {
+                            let app = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = updater::install_update(app).await {
+                                    log::error!("Update install failed: {}", e);
+                                }
+                            });
+                        }
                         "quit" => {
                             app.exit(0);
                         }
@@ -252,7 +316,19 @@ pub fn run() {
                 api.prevent_close();
             }
         })
-        .invoke_handler(tauri::generate_handler![get_printers, print_pdf])
+        .invoke_handler(tauri::generate_handler![
+            printers::get_printers,
+            printers::get_printers_detailed,
+            print_pdf,
+            print_jobs::get_print_jobs,
+            print_jobs::cancel_print_job,
+            logging::get_recent_logs,
+            logging::open_log_folder,
+            updater::check_for_updates_now,
+            updater::install_update,
+            opener::open_pdf_preview,
+            opener::open_print_queue
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
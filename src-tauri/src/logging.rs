@@ -0,0 +1,77 @@
+// Structured, always-on logging for print operations.
+//
+// `tauri_plugin_log` used to be registered only in debug builds, so a
+// production install running headless in the tray left no trace when a
+// print silently failed. Logging is always on now, and every external
+// command we spawn is logged with its argv; commands we wait on are also
+// logged with their exit code and captured output, so support can pull
+// diagnostics out of `get_recent_logs()` (or the log file itself) without a
+// console attached.
+
+use std::process::{Command, Output};
+
+/// Format a `Command` the way it would read on a shell, for logging.
+pub fn format_argv(cmd: &Command) -> String {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let args = cmd.get_args().map(|a| a.to_string_lossy().to_string());
+
+    std::iter::once(program).chain(args).collect::<Vec<_>>().join(" ")
+}
+
+/// Log an external command's outcome: info on success, error otherwise.
+/// `context` identifies what the command was for (e.g. printer name / job
+/// name / payload size), so a single log line is enough to diagnose most
+/// print failures without cross-referencing other entries.
+pub fn log_command_result(context: &str, argv: &str, output: &Output) {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if output.status.success() {
+        log::info!(
+            "{context} | argv: {argv} | exit code: {:?} | stdout: {}",
+            output.status.code(),
+            stdout.trim()
+        );
+    } else {
+        log::error!(
+            "{context} | argv: {argv} | exit code: {:?} | stdout: {} | stderr: {}",
+            output.status.code(),
+            stdout.trim(),
+            stderr.trim()
+        );
+    }
+}
+
+/// Return the last `lines` lines of the current log file, oldest first, for
+/// the in-app diagnostics view.
+#[tauri::command]
+pub fn get_recent_logs(app: tauri::AppHandle, lines: Option<usize>) -> Result<String, String> {
+    use tauri::Manager;
+
+    let log_path = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| e.to_string())?
+        .join("gls-print-agent.log");
+
+    let contents = std::fs::read_to_string(&log_path)
+        .map_err(|e| format!("Failed to read log file at {:?}: {}", log_path, e))?;
+
+    let limit = lines.unwrap_or(200);
+    let tail: Vec<&str> = contents.lines().rev().take(limit).collect();
+    Ok(tail.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+/// Open the app's log directory in the OS file browser, for support to grab
+/// diagnostics from end users without a console.
+#[tauri::command]
+pub fn open_log_folder(app: tauri::AppHandle) -> Result<(), String> {
+    open_log_folder_handle(&app)
+}
+
+pub fn open_log_folder_handle(app: &tauri::AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+
+    let log_dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    crate::opener::open_target(&log_dir.to_string_lossy())
+}
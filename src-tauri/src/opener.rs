@@ -0,0 +1,155 @@
+// Cross-platform "open with the OS default handler" helper, plus the
+// "reveal" commands that use it: `open_pdf_preview` and `open_print_queue`.
+//
+// This puts every `open`/`xdg-open`/`explorer` call (e.g. for the log
+// folder) behind one path that's environment-normalized for
+// AppImage/Flatpak/Snap packaging, so a spawned viewer doesn't inherit
+// bundle-local `LD_LIBRARY_PATH`/`PATH` entries.
+
+use std::process::Command;
+use std::sync::Mutex;
+
+use base64::Engine;
+
+use crate::{logging, sandbox_env};
+
+/// Open a path or URL with the OS default handler.
+pub fn open_target(target: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = Command::new("open");
+        cmd.arg(target);
+        spawn("open_target", &mut cmd)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("explorer");
+        cmd.arg(target);
+        spawn("open_target", &mut cmd)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        open_target_linux(target)
+    }
+}
+
+/// Prefer the GLib `AppInfo` launch path (via the `gio` CLI, which is its
+/// standard entry point) so normal `.desktop` file associations are
+/// honoured, falling back to `xdg-open` when `gio` isn't installed.
+#[cfg(target_os = "linux")]
+fn open_target_linux(target: &str) -> Result<(), String> {
+    let mut gio = Command::new("gio");
+    gio.arg("open").arg(target);
+    sandbox_env::normalize(&mut gio);
+    let argv = logging::format_argv(&gio);
+    if let Ok(output) = gio.output() {
+        logging::log_command_result("open_target_linux gio", &argv, &output);
+        if output.status.success() {
+            return Ok(());
+        }
+    }
+
+    let mut cmd = Command::new("xdg-open");
+    cmd.arg(target);
+    spawn("open_target_linux xdg-open", &mut cmd)
+}
+
+/// Spawn a fire-and-forget external command (a viewer/file manager we don't
+/// wait on), logging its argv up front since `spawn` doesn't give us an exit
+/// code or output to log afterward.
+fn spawn(context: &str, cmd: &mut Command) -> Result<(), String> {
+    sandbox_env::normalize(cmd);
+    let argv = logging::format_argv(cmd);
+    let result = cmd.spawn().map_err(|e| format!("Failed to open {:?}: {}", cmd.get_program(), e));
+    match &result {
+        Ok(_) => log::info!("{context} | argv: {argv} | spawned"),
+        Err(e) => log::error!("{context} | argv: {argv} | failed: {e}"),
+    }
+    result.map(|_| ())
+}
+
+/// How many spooled previews to keep temp directories alive for. Bounded so
+/// a tray app running for days doesn't leak a temp dir + PDF on disk per
+/// preview; `open_pdf_preview` only needs the file to survive long enough
+/// for the external viewer to open it, not for the life of the app.
+const MAX_KEPT_PREVIEWS: usize = 10;
+
+/// Keeps the most recent spooled preview PDFs' temp directories alive,
+/// instead of dropping them (and deleting the file) the instant
+/// `open_pdf_preview` returns, which could race a slow-to-launch viewer.
+#[derive(Default)]
+pub struct PreviewRegistry(Mutex<Vec<tempfile::TempDir>>);
+
+impl PreviewRegistry {
+    fn keep_alive(&self, dir: tempfile::TempDir) {
+        let mut dirs = self.0.lock().unwrap();
+        dirs.push(dir);
+
+        let excess = dirs.len().saturating_sub(MAX_KEPT_PREVIEWS);
+        if excess > 0 {
+            dirs.drain(0..excess);
+        }
+    }
+}
+
+/// Write a spooled PDF to disk and open it with the OS default PDF viewer,
+/// so a user can inspect exactly what was sent to the printer.
+#[tauri::command]
+pub fn open_pdf_preview(
+    pdf_base64: String,
+    previews: tauri::State<PreviewRegistry>,
+) -> Result<(), String> {
+    let pdf_bytes = base64::engine::general_purpose::STANDARD
+        .decode(&pdf_base64)
+        .map_err(|e| format!("Failed to decode PDF: {}", e))?;
+
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let pdf_path = temp_dir.path().join("preview.pdf");
+
+    std::fs::write(&pdf_path, &pdf_bytes).map_err(|e| format!("Failed to write PDF: {}", e))?;
+
+    open_target(&pdf_path.to_string_lossy())?;
+
+    previews.keep_alive(temp_dir);
+
+    Ok(())
+}
+
+/// Open the native OS print spooler UI for a printer. Both CUPS platforms
+/// expose this via the CUPS web interface; Windows has a dedicated queue
+/// window reachable through `printui.dll`.
+#[tauri::command]
+pub fn open_print_queue(printer_name: String) -> Result<(), String> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        open_target(&format!(
+            "http://localhost:631/printers/{}",
+            percent_encode_path_segment(&printer_name)
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("rundll32");
+        cmd.args(["printui.dll,PrintUIEntry", "/o", "/n", &printer_name]);
+        spawn("open_print_queue", &mut cmd)
+    }
+}
+
+/// Percent-encode a string for use as a single URL path segment. GLS label
+/// printer names routinely contain spaces and other characters that would
+/// otherwise produce an invalid URL or point at the wrong queue.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn percent_encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
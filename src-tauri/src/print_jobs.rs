@@ -0,0 +1,244 @@
+// In-memory print job tracking.
+//
+// `print_pdf` used to be fire-and-forget, so the UI could never tell whether
+// a job completed, stalled, or was held by the spooler. This module keeps a
+// registry of jobs we've submitted, lets callers poll their status against
+// the OS spooler, and lets them cancel a job that's stuck.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::{logging, sandbox_env};
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Printing,
+    Completed,
+    Error,
+    Canceled,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    pub printer_name: String,
+    pub job_name: String,
+    pub status: JobStatus,
+}
+
+/// Registry of print jobs we've submitted, keyed by job id. Stored as Tauri
+/// managed state so commands can share it across invocations.
+#[derive(Default)]
+pub struct JobRegistry(Mutex<HashMap<String, JobInfo>>);
+
+impl JobRegistry {
+    pub fn insert(&self, job: JobInfo) {
+        self.0.lock().unwrap().insert(job.job_id.clone(), job);
+    }
+
+    fn jobs_for_printer(&self, printer_name: &str) -> Vec<JobInfo> {
+        self.0
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| job.printer_name == printer_name)
+            .cloned()
+            .collect()
+    }
+
+    fn set_status(&self, job_id: &str, status: JobStatus) {
+        if let Some(job) = self.0.lock().unwrap().get_mut(job_id) {
+            job.status = status;
+        }
+    }
+}
+
+/// Parse the CUPS job id that `lp` prints to stdout, e.g.
+/// `request id is HP_LaserJet-42 (1 file(s))`.
+pub fn parse_cups_job_id(stdout: &str) -> Option<String> {
+    let line = stdout.lines().find(|l| l.starts_with("request id is"))?;
+    line.strip_prefix("request id is")?
+        .trim()
+        .split_whitespace()
+        .next()
+        .map(|s| s.to_string())
+}
+
+/// Look up the spooler job id WMI assigned to the job SumatraPDF just
+/// submitted, so it can be registered the same way the CUPS job id is on
+/// macOS/Linux. Returned as `"<printer>-<job id>"`, matching the CUPS
+/// format; `get_print_jobs` and `cancel_print_job` below split the numeric
+/// id back off it on Windows.
+#[cfg(target_os = "windows")]
+pub fn capture_windows_job_id(printer_name: &str) -> Option<String> {
+    let mut cmd = Command::new("powershell.exe");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "Get-WmiObject -Class Win32_PrintJob -Filter \"Name LIKE '{}%'\" | Sort-Object -Property JobId -Descending | Select-Object -First 1 -ExpandProperty JobId",
+            printer_name.replace('\'', "''")
+        ),
+    ]);
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = cmd.output().ok()?;
+    logging::log_command_result(&format!("capture_windows_job_id printer={}", printer_name), &argv, &output);
+
+    let raw_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if raw_id.is_empty() {
+        None
+    } else {
+        Some(format!("{}-{}", printer_name, raw_id))
+    }
+}
+
+/// Get the set of job ids `lpstat -o <printer>` still reports as queued on
+/// Unix (CUPS). Jobs we know about that aren't in this set have left the
+/// spooler, i.e. completed.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn queued_job_ids(printer_name: &str) -> Result<Vec<String>, String> {
+    let mut cmd = Command::new("lpstat");
+    cmd.arg("-o").arg(printer_name);
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    logging::log_command_result(&format!("queued_job_ids printer={}", printer_name), &argv, &output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|s| s.to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_print_jobs(
+    printer_name: String,
+    registry: tauri::State<JobRegistry>,
+) -> Result<Vec<JobInfo>, String> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let queued = queued_job_ids(&printer_name)?;
+        for job in registry.jobs_for_printer(&printer_name) {
+            if job.status == JobStatus::Canceled || job.status == JobStatus::Error {
+                continue;
+            }
+            if queued.contains(&job.job_id) {
+                registry.set_status(&job.job_id, JobStatus::Printing);
+            } else if job.status != JobStatus::Completed {
+                registry.set_status(&job.job_id, JobStatus::Completed);
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Get-WmiObject -Class Win32_PrintJob -Filter \"Name LIKE '{}%'\" | Select-Object -ExpandProperty JobId",
+                printer_name.replace('\'', "''")
+            ),
+        ]);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| e.to_string())?;
+        logging::log_command_result(&format!("get_print_jobs printer={}", printer_name), &argv, &output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let active_ids: Vec<String> = stdout.lines().map(|s| s.trim().to_string()).collect();
+
+        for job in registry.jobs_for_printer(&printer_name) {
+            if job.status == JobStatus::Canceled || job.status == JobStatus::Error {
+                continue;
+            }
+            // job.job_id is "<printer>-<N>"; compare the numeric tail for
+            // equality rather than a suffix match, which would also match
+            // e.g. active id "5" against stored jobs "Printer-15"/"-25".
+            let numeric_id = job.job_id.rsplit_once('-').map(|(_, id)| id);
+            let is_active = numeric_id.is_some_and(|id| active_ids.iter().any(|active| active == id));
+
+            if is_active {
+                registry.set_status(&job.job_id, JobStatus::Printing);
+            } else if job.status != JobStatus::Completed {
+                registry.set_status(&job.job_id, JobStatus::Completed);
+            }
+        }
+    }
+
+    Ok(registry.jobs_for_printer(&printer_name))
+}
+
+#[tauri::command]
+pub fn cancel_print_job(job_id: String, registry: tauri::State<JobRegistry>) -> Result<(), String> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let mut cmd = Command::new("cancel");
+        cmd.arg(&job_id);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| format!("Failed to cancel job: {}", e))?;
+        logging::log_command_result(&format!("cancel_print_job job_id={}", job_id), &argv, &output);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Cancel failed: {}", stderr));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // job_id is "<printer>-<job id>" (see capture_windows_job_id);
+        // Remove-PrintJob needs the printer name and the numeric id
+        // separately.
+        let (printer, id) = job_id
+            .rsplit_once('-')
+            .ok_or_else(|| format!("Malformed job id: {}", job_id))?;
+
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Remove-PrintJob -PrinterName '{}' -ID {}",
+                printer.replace('\'', "''"),
+                id
+            ),
+        ]);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| format!("Failed to cancel job: {}", e))?;
+        logging::log_command_result(&format!("cancel_print_job job_id={}", job_id), &argv, &output);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Cancel failed: {}", stderr));
+        }
+    }
+
+    registry.set_status(&job_id, JobStatus::Canceled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_cups_job_id;
+
+    #[test]
+    fn parses_standard_cups_request_id_line() {
+        let stdout = "request id is My_Printer-42 (1 file(s))\n";
+        assert_eq!(parse_cups_job_id(stdout), Some("My_Printer-42".to_string()));
+    }
+
+    #[test]
+    fn returns_none_when_no_request_id_line() {
+        let stdout = "lp: unable to print file\n";
+        assert_eq!(parse_cups_job_id(stdout), None);
+    }
+}
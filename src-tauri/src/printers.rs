@@ -0,0 +1,272 @@
+// Printer discovery.
+//
+// `get_printers` only returns a bare list of names, which doesn't tell the
+// UI which printer is the default, whether it's idle or offline, or what
+// media it supports - so a user can pick a printer that can't actually take
+// the label size a job needs. `get_printers_detailed` carries that context;
+// `get_printers` is kept as-is for existing callers.
+
+use std::process::Command;
+
+use crate::{logging, sandbox_env};
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterState {
+    Idle,
+    Printing,
+    Offline,
+    Unknown,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct PrinterInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub state: PrinterState,
+    pub supported_media: Vec<String>,
+}
+
+// Get list of available printers
+#[tauri::command]
+pub fn get_printers() -> Result<Vec<String>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = Command::new("lpstat");
+        cmd.arg("-e");
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| e.to_string())?;
+        logging::log_command_result("get_printers", &argv, &output);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let printers: Vec<String> = stdout
+            .lines()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Ok(printers)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Use PowerShell with WMI (works on all Windows versions including Windows 11)
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args([
+            "-NoProfile",
+            "-Command",
+            "Get-WmiObject -Class Win32_Printer | Select-Object -ExpandProperty Name"
+        ]);
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| e.to_string())?;
+        logging::log_command_result("get_printers", &argv, &output);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let printers: Vec<String> = stdout
+            .lines()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Ok(printers)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut cmd = Command::new("lpstat");
+        cmd.arg("-e");
+        sandbox_env::normalize(&mut cmd);
+        let argv = logging::format_argv(&cmd);
+        let output = cmd.output().map_err(|e| e.to_string())?;
+        logging::log_command_result("get_printers", &argv, &output);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let printers: Vec<String> = stdout
+            .lines()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Ok(printers)
+    }
+}
+
+/// Detailed printer discovery: default printer, connection state, and
+/// supported media/page sizes, so the UI can pre-select a default and warn
+/// before a job is sent to a printer that can't handle the label dimensions.
+#[tauri::command]
+pub fn get_printers_detailed() -> Result<Vec<PrinterInfo>, String> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        unix_printers_detailed()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_printers_detailed()
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn unix_printers_detailed() -> Result<Vec<PrinterInfo>, String> {
+    let mut cmd = Command::new("lpstat");
+    cmd.arg("-p").arg("-d");
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    logging::log_command_result("get_printers_detailed", &argv, &output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut default_name = None;
+    let mut printers = Vec::new();
+
+    for line in stdout.lines() {
+        if let Some(rest) = line.strip_prefix("system default destination: ") {
+            default_name = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("printer ") {
+            // `lpstat -p` uses a different phrase per state, none of which
+            // share a common separator: "X is idle.  enabled since …",
+            // "X now printing X-7.  enabled since …", "X disabled since …".
+            // Find whichever marker is present and take the name as
+            // whatever precedes it.
+            const MARKERS: [(&str, PrinterState); 3] = [
+                (" is idle", PrinterState::Idle),
+                (" now printing", PrinterState::Printing),
+                (" disabled", PrinterState::Offline),
+            ];
+
+            let found = MARKERS
+                .iter()
+                .filter_map(|(marker, state)| rest.find(marker).map(|idx| (idx, *state)))
+                .min_by_key(|(idx, _)| *idx);
+
+            let (name, state) = match found {
+                Some((idx, state)) => (rest[..idx].trim().to_string(), state),
+                None => (rest.trim().to_string(), PrinterState::Unknown),
+            };
+
+            if !name.is_empty() {
+                printers.push(PrinterInfo {
+                    name,
+                    is_default: false,
+                    state,
+                    supported_media: Vec::new(),
+                });
+            }
+        }
+    }
+
+    for printer in printers.iter_mut() {
+        printer.is_default = default_name.as_deref() == Some(printer.name.as_str());
+        printer.supported_media = unix_supported_media(&printer.name);
+    }
+
+    Ok(printers)
+}
+
+/// Supported page sizes from `lpoptions -p <printer> -l`, e.g. a line like
+/// `PageSize/Media Size: *Letter A4 Legal` -> `["Letter", "A4", "Legal"]`.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn unix_supported_media(printer_name: &str) -> Vec<String> {
+    let mut cmd = Command::new("lpoptions");
+    cmd.arg("-p").arg(printer_name).arg("-l");
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::error!("get_printers_detailed media lookup failed | argv: {} | error: {}", argv, e);
+            return Vec::new();
+        }
+    };
+    logging::log_command_result("get_printers_detailed media lookup", &argv, &output);
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.starts_with("PageSize") || line.starts_with("media"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, values)| {
+            values
+                .split_whitespace()
+                .map(|v| v.trim_start_matches('*').to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn windows_printers_detailed() -> Result<Vec<PrinterInfo>, String> {
+    let mut cmd = Command::new("powershell.exe");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        "Get-WmiObject -Class Win32_Printer | Select-Object Name,Default,PrinterStatus | ConvertTo-Json",
+    ]);
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    logging::log_command_result("get_printers_detailed", &argv, &output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value = serde_json::from_str(stdout.trim()).unwrap_or(serde_json::Value::Null);
+    let entries: Vec<serde_json::Value> = match parsed {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(_) => vec![parsed],
+        _ => Vec::new(),
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry.get("Name")?.as_str()?.to_string();
+            let is_default = entry.get("Default").and_then(|v| v.as_bool()).unwrap_or(false);
+            // Win32_Printer.PrinterStatus: 3 = Idle, 4 = Printing, 7 = Offline.
+            let state = match entry.get("PrinterStatus").and_then(|v| v.as_i64()) {
+                Some(3) => PrinterState::Idle,
+                Some(4) => PrinterState::Printing,
+                Some(7) => PrinterState::Offline,
+                _ => PrinterState::Unknown,
+            };
+            let supported_media = windows_supported_media(&name);
+
+            Some(PrinterInfo {
+                name,
+                is_default,
+                state,
+                supported_media,
+            })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "windows")]
+fn windows_supported_media(printer_name: &str) -> Vec<String> {
+    let mut cmd = Command::new("powershell.exe");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "(Get-PrinterProperty -PrinterName '{}' -PropertyName 'Config:PageMediaSize').Value",
+            printer_name.replace('\'', "''")
+        ),
+    ]);
+    sandbox_env::normalize(&mut cmd);
+    let argv = logging::format_argv(&cmd);
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::error!("get_printers_detailed media lookup failed | argv: {} | error: {}", argv, e);
+            return Vec::new();
+        }
+    };
+    logging::log_command_result("get_printers_detailed media lookup", &argv, &output);
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
@@ -0,0 +1,169 @@
+// Environment normalization for AppImage/Flatpak/Snap packaging.
+//
+// When this agent runs from a bundled Linux package, the launcher prepends
+// bundle-local paths to PATH and friends so the bundled app can find its own
+// libraries. Those same variables leak into every `Command` we spawn, which
+// makes system binaries like `lp`/`lpstat` pick up incompatible bundled
+// libraries (or the wrong `lp` entirely) and fail in ways that are very hard
+// to diagnose from a bug report. `normalize` strips bundle-local entries out
+// of a child process's environment so it behaves as if launched outside the
+// bundle. Note that the "original" value captured at `run()` start may
+// already reflect the launcher's mutation (we can't see further back than
+// our own process start), so the bundle-prefix filter below is what
+// actually does the cleaning, not the restore-original merge.
+
+use std::collections::HashSet;
+use std::env;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Environment variables that commonly carry bundle-local paths and need
+/// normalization before spawning a child process.
+const PATHLIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "GTK_PATH",
+    "XDG_DATA_DIRS",
+];
+
+/// Snapshot of the path-list variables as this process received them, plus
+/// the bundle prefix, captured once at startup.
+struct PristineEnv {
+    bundle_prefix: Option<String>,
+    vars: Vec<(String, Option<String>)>,
+}
+
+static PRISTINE_ENV: OnceLock<PristineEnv> = OnceLock::new();
+
+pub fn is_appimage() -> bool {
+    env::var_os("APPIMAGE").is_some() || env::var_os("APPDIR").is_some()
+}
+
+pub fn is_flatpak() -> bool {
+    env::var_os("FLATPAK_ID").is_some() || std::path::Path::new("/.flatpak-info").exists()
+}
+
+pub fn is_snap() -> bool {
+    env::var_os("SNAP").is_some() || env::var_os("SNAP_NAME").is_some()
+}
+
+pub fn is_sandboxed() -> bool {
+    is_appimage() || is_flatpak() || is_snap()
+}
+
+fn bundle_prefix() -> Option<String> {
+    if let Some(prefix) = env::var("APPDIR").ok().filter(|s| !s.is_empty()) {
+        return Some(prefix);
+    }
+    if let Some(prefix) = env::var("SNAP").ok().filter(|s| !s.is_empty()) {
+        return Some(prefix);
+    }
+    if is_flatpak() {
+        // Flatpak installs run under /app; unlike AppImage/Snap there's no
+        // env var carrying this path, so it's hardcoded to the standard
+        // Flatpak runtime layout.
+        return Some("/app".to_string());
+    }
+    None
+}
+
+/// Capture the current environment as "pristine". Must be called as early as
+/// possible at startup, before any bundle-aware mutation has a chance to
+/// compound (it's idempotent, so later calls are no-ops).
+pub fn capture_pristine_env() {
+    PRISTINE_ENV.get_or_init(|| PristineEnv {
+        bundle_prefix: bundle_prefix(),
+        vars: PATHLIST_VARS
+            .iter()
+            .map(|&name| (name.to_string(), env::var(name).ok()))
+            .collect(),
+    });
+}
+
+/// Split a `:`-separated path list, drop entries under `bundle_prefix`, merge
+/// in the `original` entries (as captured at startup), and de-duplicate while keeping the
+/// first occurrence of each entry.
+pub fn normalize_pathlist(current: &str, original: Option<&str>, bundle_prefix: Option<&str>) -> String {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    let current_entries = current.split(':').filter(|s| !s.is_empty());
+    let original_entries = original
+        .unwrap_or("")
+        .split(':')
+        .filter(|s| !s.is_empty());
+
+    for entry in current_entries.chain(original_entries) {
+        if let Some(prefix) = bundle_prefix {
+            if entry.starts_with(prefix) {
+                continue;
+            }
+        }
+        if seen.insert(entry.to_string()) {
+            result.push(entry.to_string());
+        }
+    }
+
+    result.join(":")
+}
+
+/// Apply environment normalization to a `Command` so it runs as if launched
+/// outside the AppImage/Flatpak/Snap sandbox. No-op when not sandboxed.
+pub fn normalize(command: &mut Command) {
+    if !is_sandboxed() {
+        return;
+    }
+
+    capture_pristine_env();
+    let Some(pristine) = PRISTINE_ENV.get() else {
+        return;
+    };
+
+    for (name, original) in &pristine.vars {
+        let current = env::var(name).unwrap_or_default();
+        let normalized = normalize_pathlist(&current, original.as_deref(), pristine.bundle_prefix.as_deref());
+
+        if normalized.is_empty() {
+            command.env_remove(name);
+        } else {
+            command.env(name, normalized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_pathlist;
+
+    #[test]
+    fn drops_bundle_entries_and_restores_original() {
+        let current = "/tmp/.mount_app/usr/bin:/usr/bin:/tmp/.mount_app/usr/local/bin";
+        let original = Some("/usr/local/bin:/usr/bin");
+        let bundle = Some("/tmp/.mount_app");
+
+        let normalized = normalize_pathlist(current, original, bundle);
+
+        assert_eq!(normalized, "/usr/bin:/usr/local/bin");
+    }
+
+    #[test]
+    fn dedupes_keeping_first_occurrence() {
+        let current = "/usr/bin:/usr/local/bin";
+        let original = Some("/usr/local/bin:/usr/bin");
+
+        let normalized = normalize_pathlist(current, original, None);
+
+        assert_eq!(normalized, "/usr/bin:/usr/local/bin");
+    }
+
+    #[test]
+    fn empty_when_nothing_survives() {
+        let current = "/tmp/.mount_app/usr/bin";
+        let bundle = Some("/tmp/.mount_app");
+
+        let normalized = normalize_pathlist(current, None, bundle);
+
+        assert_eq!(normalized, "");
+    }
+}
@@ -0,0 +1,123 @@
+// Self-update subsystem, built on tauri-plugin-updater.
+//
+// This agent sits in the system tray on many machines with no other update
+// path, so shipping a fix to the Windows SumatraPDF invocation or the Linux
+// `lp` handling used to mean walking around reinstalling by hand. We check
+// the release endpoint on startup and on a timer, and expose "Check for
+// updates…" / "Install update" from the tray menu, plus matching commands so
+// the main window can drive the flow and show progress for unattended kiosk
+// installs.
+//
+// This module only drives the check/download/install flow; the updater
+// endpoint URL, signing pubkey, and per-platform bundle targets it relies on
+// are configured in `tauri.conf.json`, which is packaging config owned by
+// the release pipeline rather than this crate. Until that config ships,
+// `app.updater()` returns an error on every check - handled below as a
+// routine "not configured yet" case rather than a failure.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_updater::UpdaterExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The update we've found pending, if any, so "Install update" can act on it
+/// without re-checking the release endpoint.
+#[derive(Default)]
+pub struct UpdateState(Mutex<Option<tauri_plugin_updater::Update>>);
+
+/// Kick off the startup check, then re-check on a timer for the lifetime of
+/// the app. Runs on a plain OS thread rather than an async interval timer,
+/// since `tauri::async_runtime` doesn't re-export `tokio::time` and this
+/// crate doesn't otherwise depend on tokio directly.
+pub fn start_update_checks(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        tauri::async_runtime::block_on(check_for_updates(&app));
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+async fn check_for_updates(app: &tauri::AppHandle) {
+    let updater = match app.updater() {
+        Ok(updater) => updater,
+        Err(e) => {
+            // Expected until the bundle config ships an updater endpoint +
+            // pubkey (tracked as packaging work, not a code bug) - log at
+            // warn rather than error so it doesn't page anyone every
+            // CHECK_INTERVAL tick.
+            log::warn!("Updater not configured, skipping check: {}", e);
+            return;
+        }
+    };
+
+    match updater.check().await {
+        Ok(Some(update)) => {
+            log::info!(
+                "Update available: {} -> {}",
+                update.current_version,
+                update.version
+            );
+            *app.state::<UpdateState>().0.lock().unwrap() = Some(update);
+        }
+        Ok(None) => log::info!("No update available"),
+        Err(e) => log::error!("Update check failed: {}", e),
+    }
+}
+
+/// Trigger an immediate check, for the tray menu and the "Check for
+/// updates…" action. Returns whether an update is now pending.
+#[tauri::command]
+pub async fn check_for_updates_now(app: tauri::AppHandle) -> Result<bool, String> {
+    check_for_updates(&app).await;
+    Ok(app.state::<UpdateState>().0.lock().unwrap().is_some())
+}
+
+#[derive(Clone, serde::Serialize)]
+struct UpdateProgress {
+    downloaded: usize,
+    total: Option<u64>,
+}
+
+/// Download and install the pending update, then restart into it. Emits
+/// `update://download-progress` / `update://installing` so the main window
+/// can show progress during unattended kiosk installs.
+#[tauri::command]
+pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
+    let update = app
+        .state::<UpdateState>()
+        .0
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| "No pending update found; check for updates first".to_string())?;
+
+    let mut downloaded = 0usize;
+    let progress_app = app.clone();
+    let installing_app = app.clone();
+    let install_result = update
+        .download_and_install(
+            move |chunk_len, total| {
+                downloaded += chunk_len;
+                log::info!("Downloading update: {}/{:?} bytes", downloaded, total);
+                let _ = progress_app.emit("update://download-progress", UpdateProgress { downloaded, total });
+            },
+            move || {
+                log::info!("Update downloaded, installing");
+                let _ = installing_app.emit("update://installing", ());
+            },
+        )
+        .await;
+
+    match install_result {
+        Ok(()) => app.restart(),
+        Err(e) => {
+            // Put the update back so a retry doesn't have to wait for the
+            // next timer tick (or a manual re-check) to rediscover it.
+            *app.state::<UpdateState>().0.lock().unwrap() = Some(update);
+            Err(format!("Failed to install update: {}", e))
+        }
+    }
+}